@@ -1,6 +1,7 @@
 use http::Method;
 use route_recognizer::{Match, Params, Router as MethodRouter};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::endpoint::{DynEndpoint, Endpoint};
 use crate::utils::BoxFuture;
@@ -8,20 +9,268 @@ use crate::{Request, Response};
 
 /// The routing table used by `Server`
 ///
-/// Internally, we have a separate state machine per http method; indexing
-/// by the method first allows the table itself to be more efficient.
+/// Internally, routes are bucketed first by the host pattern they were
+/// registered for, then by http method, and finally by path. Selecting the
+/// host bucket first lets a single `Server` serve several domains with
+/// independent route tables; requests whose `Host` header matches no explicit
+/// pattern fall back to the catch-all bucket, which preserves the behavior of
+/// a host-agnostic router.
 #[allow(missing_debug_implementations)]
 pub(crate) struct Router<State> {
-    method_map: HashMap<http::Method, MethodRouter<Box<DynEndpoint<State>>>>,
+    /// Explicitly host-scoped route tables, checked in registration order.
+    hosts: Vec<(HostPattern, RouteTable<State>)>,
+    /// The table used when no host pattern matches (or none were registered).
+    catch_all: RouteTable<State>,
+    /// Endpoint invoked when no route matches; defaults to a bare 404.
+    fallback: Option<Box<DynEndpoint<State>>>,
+    /// Endpoint invoked for the synthesized 405 responses; the `Allow` header
+    /// is still populated on top of whatever it returns.
+    method_not_allowed: Option<Arc<DynEndpoint<State>>>,
+    /// How to react when a path only matches after cleanup; off by default.
+    normalize: Normalization,
+    /// Whether path cleanup also lowercases the path.
+    normalize_lowercase: bool,
+}
+
+/// How `Router` reacts to a request path that only matches a registered route
+/// after cleanup (collapsing duplicate slashes, resolving `.`/`..` segments,
+/// and toggling a trailing slash).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Leave request paths untouched (the default, preserving current
+    /// semantics).
+    Off,
+    /// Redirect the client to the canonical path (`301` for GET/HEAD, `308`
+    /// otherwise so the method and body are preserved).
+    Redirect,
+    /// Silently dispatch the endpoint registered at the canonical path without
+    /// a round-trip.
+    Silent,
+}
+
+/// A predicate over a request, used to disambiguate several endpoints sharing
+/// the same path and method.
+///
+/// When more than one endpoint is registered for a path+method, `route`
+/// iterates the candidates in registration order and dispatches to the first
+/// whose matcher returns `true`. This is the extension point behind
+/// content-negotiation matchers such as [`AcceptMatcher`] and
+/// [`ContentTypeMatcher`].
+pub trait RouteMatcher<State>: Send + Sync + 'static {
+    /// Does this matcher accept `req`?
+    fn is_match(&self, req: &Request<State>) -> bool;
+}
+
+/// A matcher that accepts every request; the default for endpoints registered
+/// without an explicit matcher.
+struct AllMatcher;
+
+impl<State> RouteMatcher<State> for AllMatcher {
+    fn is_match(&self, _req: &Request<State>) -> bool {
+        true
+    }
+}
+
+/// Requires both inner matchers to accept the request. Use it to combine, for
+/// instance, an [`AcceptMatcher`] with a [`ContentTypeMatcher`].
+pub struct And<A, B>(pub A, pub B);
+
+impl<State, A, B> RouteMatcher<State> for And<A, B>
+where
+    A: RouteMatcher<State>,
+    B: RouteMatcher<State>,
+{
+    fn is_match(&self, req: &Request<State>) -> bool {
+        self.0.is_match(req) && self.1.is_match(req)
+    }
+}
+
+/// Negotiates on the request's `Accept` header against the content types the
+/// endpoint declares it can produce. A request with no `Accept` header is
+/// treated as accepting anything.
+pub struct AcceptMatcher {
+    produces: Vec<String>,
+}
+
+impl AcceptMatcher {
+    /// Build a matcher for the set of content types the endpoint can produce,
+    /// e.g. `AcceptMatcher::new(vec!["application/json"])`.
+    pub fn new<I, S>(content_types: I) -> AcceptMatcher
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        AcceptMatcher {
+            produces: content_types.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<State> RouteMatcher<State> for AcceptMatcher {
+    fn is_match(&self, req: &Request<State>) -> bool {
+        let accept = match header_str(req, http::header::ACCEPT) {
+            Some(accept) => accept,
+            // No `Accept` header means the client accepts any representation.
+            None => return true,
+        };
+        let ranges = parse_media_ranges(&accept);
+        // Per RFC 7231 the most specific matching media range wins: for each
+        // producible type, find the range with the highest specificity that
+        // covers it and let that range's `q` decide. The endpoint is acceptable
+        // if any producible type ends up with a positive quality.
+        self.produces.iter().any(|produced| {
+            ranges
+                .iter()
+                .filter(|range| range.matches(produced))
+                .max_by(|a, b| {
+                    a.specificity().cmp(&b.specificity()).then_with(|| {
+                        a.q.partial_cmp(&b.q).unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                })
+                .is_some_and(|range| range.q > 0.0)
+        })
+    }
+}
+
+/// Matches requests whose `Content-Type` falls within one of the accepted
+/// media ranges, e.g. to route form submissions and JSON bodies to different
+/// endpoints.
+pub struct ContentTypeMatcher {
+    accepted: Vec<String>,
+}
+
+impl ContentTypeMatcher {
+    /// Build a matcher accepting the given media ranges (exact types or
+    /// `type/*` / `*/*` wildcards).
+    pub fn new<I, S>(content_types: I) -> ContentTypeMatcher
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ContentTypeMatcher {
+            accepted: content_types.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<State> RouteMatcher<State> for ContentTypeMatcher {
+    fn is_match(&self, req: &Request<State>) -> bool {
+        let content_type = match header_str(req, http::header::CONTENT_TYPE) {
+            Some(content_type) => content_type,
+            // Without a `Content-Type` there is nothing to negotiate on.
+            None => return false,
+        };
+        // Strip any `; charset=...` parameters before comparing.
+        let content_type = content_type.split(';').next().unwrap_or("").trim();
+        self.accepted
+            .iter()
+            .any(|pattern| media_range(pattern).matches(content_type))
+    }
+}
+
+/// A host pattern used to scope a bucket of routes to one or more domains.
+enum HostPattern {
+    /// Matches a single host exactly, e.g. `example.com`.
+    Exact(String),
+    /// Matches any subdomain of the given base, e.g. `*.example.com` matches
+    /// `api.example.com` (but not the bare `example.com`).
+    Wildcard(String),
+}
+
+impl HostPattern {
+    /// Build a pattern from the textual form used at registration time.
+    ///
+    /// A leading `*.` denotes a wildcard subdomain match; anything else is
+    /// treated as an exact host.
+    fn parse(host: &str) -> HostPattern {
+        if let Some(base) = host.strip_prefix("*.") {
+            HostPattern::Wildcard(base.to_lowercase())
+        } else {
+            HostPattern::Exact(host.to_lowercase())
+        }
+    }
+
+    /// Does `host` (the value of the request's `Host` header) belong to this
+    /// pattern? Matching is case-insensitive, as hostnames are, and any
+    /// `:port` suffix is ignored.
+    fn matches(&self, host: &str) -> bool {
+        let host = host.split(':').next().unwrap_or(host).to_lowercase();
+        match self {
+            HostPattern::Exact(h) => h == &host,
+            HostPattern::Wildcard(base) => host
+                .strip_suffix(base.as_str())
+                .and_then(|prefix| prefix.strip_suffix('.'))
+                .is_some_and(|sub| !sub.is_empty()),
+        }
+    }
+
+    /// Whether two patterns refer to the same host bucket.
+    fn is(&self, other: &HostPattern) -> bool {
+        match (self, other) {
+            (HostPattern::Exact(a), HostPattern::Exact(b)) => a == b,
+            (HostPattern::Wildcard(a), HostPattern::Wildcard(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// An endpoint paired with the matcher that guards it.
+struct MatchedEndpoint<State> {
+    matcher: Box<dyn RouteMatcher<State>>,
+    endpoint: Box<DynEndpoint<State>>,
+}
+
+/// A single host's routes: a separate state machine per http method, indexed
+/// by method first so the table itself can be more efficient. Each recognized
+/// path points at a list of candidate endpoints, tried in turn by their
+/// matchers.
+struct RouteTable<State> {
+    method_map: HashMap<http::Method, MethodRouter<usize>>,
+    endpoints: Vec<Vec<MatchedEndpoint<State>>>,
+    /// Maps each exact registered (method, path) to its candidate list, so that
+    /// a second registration for the *same* path extends the list rather than
+    /// being merged into an overlapping param route by `recognize`.
+    endpoint_ids: HashMap<(http::Method, String), usize>,
     paths: HashSet<String>,
 }
 
 /// The result of routing a URL
 pub(crate) struct Selection<'a, State> {
-    pub(crate) endpoint: &'a DynEndpoint<State>,
+    endpoint: SelectedEndpoint<'a, State>,
     pub(crate) params: Params,
 }
 
+/// The endpoint chosen by `route`: either borrowed from the routing table or,
+/// for synthesized responses such as normalization redirects, owned outright.
+enum SelectedEndpoint<'a, State> {
+    Shared(&'a DynEndpoint<State>),
+    Owned(Box<DynEndpoint<State>>),
+}
+
+impl<'a, State> Selection<'a, State> {
+    fn shared(endpoint: &'a DynEndpoint<State>, params: Params) -> Selection<'a, State> {
+        Selection {
+            endpoint: SelectedEndpoint::Shared(endpoint),
+            params,
+        }
+    }
+
+    fn owned(endpoint: Box<DynEndpoint<State>>, params: Params) -> Selection<'a, State> {
+        Selection {
+            endpoint: SelectedEndpoint::Owned(endpoint),
+            params,
+        }
+    }
+
+    /// The endpoint selected for this request.
+    pub(crate) fn endpoint(&self) -> &DynEndpoint<State> {
+        match &self.endpoint {
+            SelectedEndpoint::Shared(endpoint) => *endpoint,
+            SelectedEndpoint::Owned(endpoint) => &**endpoint,
+        }
+    }
+}
+
 static HTTP_METHODS: [Method; 9] = [
     Method::GET,
     Method::POST,
@@ -37,16 +286,247 @@ static HTTP_METHODS: [Method; 9] = [
 impl<State: 'static> Router<State> {
     pub(crate) fn new() -> Router<State> {
         Router {
+            hosts: Vec::new(),
+            catch_all: RouteTable::new(),
+            fallback: None,
+            method_not_allowed: None,
+            normalize: Normalization::Off,
+            normalize_lowercase: false,
+        }
+    }
+
+    pub(crate) fn add(&mut self, path: &str, method: http::Method, ep: impl Endpoint<State>) {
+        self.catch_all.add(path, method, ep);
+    }
+
+    /// Register an endpoint guarded by `matcher`. Several endpoints may share a
+    /// path and method as long as their matchers disambiguate them; see
+    /// [`RouteMatcher`].
+    pub(crate) fn add_with_matcher(
+        &mut self,
+        path: &str,
+        method: http::Method,
+        matcher: impl RouteMatcher<State>,
+        ep: impl Endpoint<State>,
+    ) {
+        self.catch_all.add_with_matcher(path, method, matcher, ep);
+    }
+
+    /// Mount a single endpoint under several paths in one call, e.g.
+    /// `add_multi(&["/posts", "/articles"], method, ep)`. The endpoint is
+    /// shared across the aliases, and each path is tracked so the default
+    /// OPTIONS/405 synthesis treats them identically.
+    pub(crate) fn add_multi(
+        &mut self,
+        paths: &[&str],
+        method: http::Method,
+        ep: impl Endpoint<State>,
+    ) {
+        self.catch_all.add_multi(paths, method, ep);
+    }
+
+    /// Register an endpoint scoped to a host pattern. `host` accepts an exact
+    /// host (`example.com`) or a wildcard subdomain (`*.example.com`); requests
+    /// whose `Host` header matches the pattern are routed against this bucket
+    /// only; the catch-all table is not consulted for them, even on a miss.
+    pub(crate) fn add_for_host(
+        &mut self,
+        host: &str,
+        path: &str,
+        method: http::Method,
+        ep: impl Endpoint<State>,
+    ) {
+        self.host_table(host).add(path, method, ep);
+    }
+
+    /// Locate (creating if necessary) the route table for `host`.
+    fn host_table(&mut self, host: &str) -> &mut RouteTable<State> {
+        let pattern = HostPattern::parse(host);
+        match self.hosts.iter().position(|(p, _)| p.is(&pattern)) {
+            Some(index) => &mut self.hosts[index].1,
+            None => {
+                self.hosts.push((pattern, RouteTable::new()));
+                &mut self.hosts.last_mut().unwrap().1
+            }
+        }
+    }
+
+    /// Register the endpoint invoked when no route matches the request. It
+    /// replaces the built-in 404 handler used by `route`.
+    pub(crate) fn set_fallback(&mut self, ep: impl Endpoint<State>) {
+        self.fallback = Some(Box::new(move |cx| Box::pin(ep.call(cx))));
+    }
+
+    /// Register the endpoint invoked when a path exists but the request method
+    /// does not. The synthesized handler still sets the `Allow` header listing
+    /// the supported methods on top of the response the endpoint returns.
+    pub(crate) fn set_method_not_allowed(&mut self, ep: impl Endpoint<State>) {
+        self.method_not_allowed = Some(Arc::new(move |cx| Box::pin(ep.call(cx))));
+    }
+
+    /// Configure how the router reacts to paths that only match after cleanup.
+    /// Defaults to [`Normalization::Off`].
+    pub(crate) fn set_normalization(&mut self, mode: Normalization) {
+        self.normalize = mode;
+    }
+
+    /// Whether path cleanup also lowercases the request path. Only has an
+    /// effect when normalization is enabled.
+    pub(crate) fn set_path_lowercasing(&mut self, lowercase: bool) {
+        self.normalize_lowercase = lowercase;
+    }
+
+    /// For each host bucket, synthesize the default OPTIONS/405 handlers.
+    pub(crate) fn add_default_handlers(&mut self) {
+        for (_, table) in &mut self.hosts {
+            table.add_default_handlers(self.method_not_allowed.clone());
+        }
+        self.catch_all
+            .add_default_handlers(self.method_not_allowed.clone());
+    }
+
+    pub(crate) fn route(
+        &self,
+        host: Option<&str>,
+        path: &str,
+        method: http::Method,
+        req: &Request<State>,
+    ) -> Selection<'_, State> {
+        // Select the first registered host bucket whose pattern matches the
+        // request's `Host` header, falling back to the catch-all table.
+        let table = host
+            .and_then(|host| {
+                self.hosts
+                    .iter()
+                    .find(|(pattern, _)| pattern.matches(host))
+                    .map(|(_, table)| table)
+            })
+            .unwrap_or(&self.catch_all);
+        let fallback: &DynEndpoint<State> = match &self.fallback {
+            Some(ep) => &**ep,
+            None => &not_found_endpoint,
+        };
+
+        // On a genuine path miss, optionally retry against a cleaned-up path and
+        // either redirect to it or dispatch it silently.
+        if self.normalize != Normalization::Off && !table.recognizes(path, &method) {
+            if let Some(canonical) = self.fixed_path(table, path, &method) {
+                match self.normalize {
+                    Normalization::Redirect => {
+                        // Keep the original query string on the canonical target.
+                        let location = match req.uri().query() {
+                            Some(query) => format!("{}?{}", canonical, query),
+                            None => canonical,
+                        };
+                        return Selection::owned(
+                            redirect_endpoint(location, &method),
+                            Params::new(),
+                        );
+                    }
+                    Normalization::Silent => return table.route(&canonical, method, fallback, req),
+                    Normalization::Off => unreachable!(),
+                }
+            }
+        }
+
+        table.route(path, method, fallback, req)
+    }
+
+    /// Find a cleaned-up form of `path` that resolves to a registered route for
+    /// `method`, trying it both with and without a trailing slash.
+    fn fixed_path(
+        &self,
+        table: &RouteTable<State>,
+        path: &str,
+        method: &http::Method,
+    ) -> Option<String> {
+        let cleaned = clean_path(path, self.normalize_lowercase);
+        let with_slash = if cleaned == "/" {
+            cleaned.clone()
+        } else {
+            format!("{}/", cleaned)
+        };
+        for candidate in [cleaned, with_slash] {
+            if candidate != path && table.recognizes(&candidate, method) {
+                return Some(candidate);
+            }
+        }
+        None
+    }
+}
+
+impl<State: 'static> RouteTable<State> {
+    fn new() -> RouteTable<State> {
+        RouteTable {
             method_map: HashMap::default(),
+            endpoints: Vec::new(),
+            endpoint_ids: HashMap::default(),
             paths: HashSet::default(),
         }
     }
 
-    pub(crate) fn add(&mut self, path: &str, method: http::Method, ep: impl Endpoint<State>) {
-        self.method_map
-            .entry(method.clone())
-            .or_insert_with(MethodRouter::new)
-            .add(path, Box::new(move |cx| Box::pin(ep.call(cx))));
+    fn add(&mut self, path: &str, method: http::Method, ep: impl Endpoint<State>) {
+        self.add_with_matcher(path, method, AllMatcher, ep);
+    }
+
+    fn add_with_matcher(
+        &mut self,
+        path: &str,
+        method: http::Method,
+        matcher: impl RouteMatcher<State>,
+        ep: impl Endpoint<State>,
+    ) {
+        self.add_boxed(
+            path,
+            method,
+            Box::new(matcher),
+            Box::new(move |cx| Box::pin(ep.call(cx))),
+        );
+    }
+
+    fn add_multi(&mut self, paths: &[&str], method: http::Method, ep: impl Endpoint<State>) {
+        // Endpoints are boxed into `DynEndpoint` closures, so to mount one under
+        // several paths we share it behind an `Arc` and hand each path its own
+        // closure invoking the same handler.
+        let ep = Arc::new(ep);
+        for path in paths {
+            let ep = ep.clone();
+            self.add_boxed(
+                path,
+                method.clone(),
+                Box::new(AllMatcher),
+                Box::new(move |cx| Box::pin(ep.call(cx))),
+            );
+        }
+    }
+
+    /// Core registration routine shared by the public `add` variants and the
+    /// synthesized default handlers. Candidates for the same path+method are
+    /// appended so that `route` can pick between them by matcher.
+    fn add_boxed(
+        &mut self,
+        path: &str,
+        method: http::Method,
+        matcher: Box<dyn RouteMatcher<State>>,
+        endpoint: Box<DynEndpoint<State>>,
+    ) {
+        let candidate = MatchedEndpoint { matcher, endpoint };
+        // Dedupe on the exact registered path string, not on `recognize`, so a
+        // concrete route is not merged into an overlapping param route's
+        // candidate list (which would shadow it).
+        let key = (method.clone(), path.to_string());
+        match self.endpoint_ids.get(&key) {
+            Some(&id) => self.endpoints[id].push(candidate),
+            None => {
+                let id = self.endpoints.len();
+                self.endpoints.push(vec![candidate]);
+                self.endpoint_ids.insert(key, id);
+                self.method_map
+                    .entry(method)
+                    .or_insert_with(MethodRouter::new)
+                    .add(path, id);
+            }
+        }
         // It is not possible (or quite cumbersome) to retrieve the set of paths
         // from `MethodRouter` - we'll keep track of them in a separate collection
         self.paths.insert(path.to_string());
@@ -58,7 +538,7 @@ impl<State: 'static> Router<State> {
     /// - for each HTTP method that doesn't have a handler, add a default handler
     ///   that returns a 405, listing the supported HTTP methods in the Allow header.
     ///   We don't add an explicit 405 for HEAD, because we fallback on GET if missing.
-    pub(crate) fn add_default_handlers(&mut self) {
+    fn add_default_handlers(&mut self, method_not_allowed: Option<Arc<DynEndpoint<State>>>) {
         for path in self.paths.clone().iter() {
             let mut http_methods_with_handlers = self.get_http_methods_with_handlers(path);
 
@@ -76,6 +556,7 @@ impl<State: 'static> Router<State> {
                         path,
                         http_method,
                         &http_methods_with_handlers,
+                        method_not_allowed.clone(),
                     )
                 }
             }
@@ -83,34 +564,39 @@ impl<State: 'static> Router<State> {
     }
 
     // Register a default `Method Not Allowed` handler: it returns a 405 with an Allow header
-    // specifying the list of supported HTTP methods for `path`.
+    // specifying the list of supported HTTP methods for `path`. If a user-supplied
+    // method-not-allowed endpoint is set, its response is used instead, with the Allow
+    // header layered on top.
     fn add_method_not_allowed_handler(
         &mut self,
         path: &str,
         method: &http::Method,
         supported_http_methods: &HashSet<http::Method>,
+        method_not_allowed: Option<Arc<DynEndpoint<State>>>,
     ) {
         let allow_header = supported_http_methods
             .into_iter()
             .map(|m| format!("{}", m))
             .collect::<Vec<_>>()
             .join(", ");
-        self.method_map
-            .entry(method.to_owned())
-            .or_insert_with(MethodRouter::new)
-            .add(
-                path,
-                Box::new(move |_| {
-                    // Only way to get this to compile apparently.
-                    let allow_header = allow_header.clone();
-                    Box::pin(async move {
-                        let response = crate::Response::new(405)
-                            .set_header("Allow", allow_header.clone())
-                            .body(http_service::Body::empty());
-                        response
-                    })
-                }),
-            );
+        self.add_boxed(
+            path,
+            method.to_owned(),
+            Box::new(AllMatcher),
+            Box::new(move |cx| {
+                // Only way to get this to compile apparently.
+                let allow_header = allow_header.clone();
+                let method_not_allowed = method_not_allowed.clone();
+                Box::pin(async move {
+                    match method_not_allowed {
+                        Some(ep) => ep(cx).await.set_header("Allow", allow_header),
+                        None => crate::Response::new(405)
+                            .set_header("Allow", allow_header)
+                            .body(http_service::Body::empty()),
+                    }
+                })
+            }),
+        );
     }
 
     // Register a default OPTIONS handler: it returns a 204 with an Allow header
@@ -125,22 +611,21 @@ impl<State: 'static> Router<State> {
             .map(|m| format!("{}", m))
             .collect::<Vec<_>>()
             .join(", ");
-        self.method_map
-            .entry(http::Method::OPTIONS)
-            .or_insert_with(MethodRouter::new)
-            .add(
-                path,
-                Box::new(move |_| {
-                    // Only way to get this to compile apparently.
-                    let allow_header = allow_header.clone();
-                    Box::pin(async move {
-                        let response = crate::Response::new(204)
-                            .set_header("Allow", allow_header.clone())
-                            .body(http_service::Body::empty());
-                        response
-                    })
-                }),
-            );
+        self.add_boxed(
+            path,
+            http::Method::OPTIONS,
+            Box::new(AllMatcher),
+            Box::new(move |_| {
+                // Only way to get this to compile apparently.
+                let allow_header = allow_header.clone();
+                Box::pin(async move {
+                    let response = crate::Response::new(204)
+                        .set_header("Allow", allow_header.clone())
+                        .body(http_service::Body::empty());
+                    response
+                })
+            }),
+        );
     }
 
     // Determine for which HTTP methods there is a registered handler for `path`
@@ -157,30 +642,177 @@ impl<State: 'static> Router<State> {
         http_methods_with_handler
     }
 
-    pub(crate) fn route(&self, path: &str, method: http::Method) -> Selection<'_, State> {
+    fn route<'a>(
+        &'a self,
+        path: &str,
+        method: http::Method,
+        fallback: &'a DynEndpoint<State>,
+        req: &Request<State>,
+    ) -> Selection<'a, State> {
         if let Some(Match { handler, params }) = self
             .method_map
             .get(&method)
             .and_then(|r| r.recognize(path).ok())
         {
-            Selection {
-                endpoint: &**handler,
-                params,
+            // A path+method may have several candidate endpoints; dispatch to
+            // the first whose matcher accepts the request.
+            for candidate in &self.endpoints[*handler] {
+                if candidate.matcher.is_match(req) {
+                    return Selection::shared(&*candidate.endpoint, params);
+                }
             }
+            // The route exists but none of the candidates could satisfy the
+            // request (e.g. its `Accept` header) - this is a 406.
+            Selection::shared(&not_acceptable_endpoint, params)
         } else if method == http::Method::HEAD {
             // If it is a HTTP HEAD request then check if there is a callback in the endpoints map
             // if not then fallback to the behavior of HTTP GET else proceed as usual
 
-            self.route(path, http::Method::GET)
+            self.route(path, http::Method::GET, fallback, req)
         } else {
-            Selection {
-                endpoint: &not_found_endpoint,
-                params: Params::new(),
+            Selection::shared(fallback, Params::new())
+        }
+    }
+
+    /// Whether some endpoint is registered for `path` under `method` (with the
+    /// usual HEAD-to-GET fallback), irrespective of matcher acceptance.
+    fn recognizes(&self, path: &str, method: &http::Method) -> bool {
+        let recognized = |method: &http::Method| {
+            self.method_map
+                .get(method)
+                .is_some_and(|r| r.recognize(path).is_ok())
+        };
+        recognized(method) || (*method == http::Method::HEAD && recognized(&http::Method::GET))
+    }
+}
+
+/// A parsed `Accept` media range, e.g. `text/*;q=0.8`.
+struct MediaRange {
+    type_: String,
+    subtype: String,
+    q: f32,
+}
+
+impl MediaRange {
+    /// Does this range cover the concrete `type/subtype` content type?
+    fn matches(&self, content_type: &str) -> bool {
+        let (type_, subtype) = split_media_type(content_type);
+        (self.type_ == "*" || self.type_.eq_ignore_ascii_case(type_))
+            && (self.subtype == "*" || self.subtype.eq_ignore_ascii_case(subtype))
+    }
+
+    /// How specific this range is: a fully-qualified `type/subtype` beats a
+    /// `type/*` range, which in turn beats `*/*`. Used to pick the range whose
+    /// `q` governs a given content type.
+    fn specificity(&self) -> u8 {
+        match (self.type_ == "*", self.subtype == "*") {
+            (false, false) => 2,
+            (false, true) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Split a `type/subtype` string, ignoring any trailing parameters.
+fn split_media_type(content_type: &str) -> (&str, &str) {
+    let content_type = content_type.split(';').next().unwrap_or("").trim();
+    let mut parts = content_type.splitn(2, '/');
+    let type_ = parts.next().unwrap_or("");
+    let subtype = parts.next().unwrap_or("*");
+    (type_, subtype)
+}
+
+/// Build a `MediaRange` from a pattern, defaulting its quality to 1.
+fn media_range(pattern: &str) -> MediaRange {
+    let (type_, subtype) = split_media_type(pattern);
+    MediaRange {
+        type_: type_.to_string(),
+        subtype: subtype.to_string(),
+        q: 1.0,
+    }
+}
+
+/// Parse an `Accept` header into its media ranges, each with its `q` value.
+fn parse_media_ranges(header: &str) -> Vec<MediaRange> {
+    header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let (type_, subtype) = split_media_type(parts.next().unwrap_or("").trim());
+            if type_.is_empty() {
+                return None;
             }
+            let q = parts
+                .find_map(|param| {
+                    let param = param.trim();
+                    param.strip_prefix("q=").and_then(|v| v.parse::<f32>().ok())
+                })
+                .unwrap_or(1.0);
+            Some(MediaRange {
+                type_: type_.to_string(),
+                subtype: subtype.to_string(),
+                q,
+            })
+        })
+        .collect()
+}
+
+/// Read a request header as a string slice copy, if present and valid UTF-8.
+fn header_str<State>(req: &Request<State>, name: http::header::HeaderName) -> Option<String> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+/// Clean a request path: collapse duplicate slashes, resolve `.`/`..`
+/// segments, and optionally lowercase it. The result keeps its leading slash
+/// and carries no trailing slash (except for the root path).
+fn clean_path(path: &str, lowercase: bool) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
         }
     }
+    let mut cleaned = String::from("/");
+    cleaned.push_str(&segments.join("/"));
+    if lowercase {
+        cleaned = cleaned.to_lowercase();
+    }
+    cleaned
+}
+
+/// Build a one-shot endpoint that redirects to `location`. GET/HEAD requests
+/// get a `301`; other methods get a `308` so the method and body survive the
+/// redirect.
+fn redirect_endpoint<State: 'static>(
+    location: String,
+    method: &http::Method,
+) -> Box<DynEndpoint<State>> {
+    let status = if *method == http::Method::GET || *method == http::Method::HEAD {
+        301
+    } else {
+        308
+    };
+    Box::new(move |_| {
+        let location = location.clone();
+        Box::pin(async move {
+            crate::Response::new(status)
+                .set_header("Location", location)
+                .body(http_service::Body::empty())
+        })
+    })
 }
 
 fn not_found_endpoint<State>(_cx: Request<State>) -> BoxFuture<'static, Response> {
     Box::pin(async move { Response::new(http::StatusCode::NOT_FOUND.as_u16()) })
 }
+
+fn not_acceptable_endpoint<State>(_cx: Request<State>) -> BoxFuture<'static, Response> {
+    Box::pin(async move { Response::new(http::StatusCode::NOT_ACCEPTABLE.as_u16()) })
+}